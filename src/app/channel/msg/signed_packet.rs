@@ -6,6 +6,7 @@
 //! ```pb3
 //! message SignedPacket {
 //!     join link msgid;
+//!     absorb trytes payload_type;
 //!     absorb trytes public_payload;
 //!     mask trytes masked_payload;
 //!     commit;
@@ -14,10 +15,27 @@
 //! }
 //! ```
 //!
+//! Before `commit`, the message also absorbs `TYPE` (`"MAM9SIGNEDPACKET"`)
+//! externally as a domain-separation tag, via [`pb3::domain::absorb_domain`];
+//! this is not shown in the command list above since it is never written to
+//! the wrap buffer. Together with `payload_type`, it binds the signed hash to
+//! this message type, so a signature computed here cannot be replayed against
+//! another message type or application that happens to reach the same
+//! spongos state. See `pb3::domain` for the general rationale.
+//!
+//! `TYPE`, `Fields`, `sizeof`, `wrap`, `unwrap_verify` and `unwrap_recover`
+//! below are generated by [`pb3_message!`](crate::pb3_message) from the
+//! schema above plus a `domain;` line for the domain tag; see that macro's docs
+//! if you're adding a sibling message type.
+//!
 //! # Fields
 //!
 //! * `msgid` -- link to the base message.
 //!
+//! * `payload_type` -- application-specific codec identifier for the
+//! payloads, authenticated by the signature so a verifier can reject a
+//! packet of a type it doesn't expect.
+//!
 //! * `public_payload` -- public part of payload.
 //!
 //! * `masked_payload` -- masked part of payload.
@@ -27,130 +45,27 @@
 //! * `sig` -- message signature generated with one of channel owner's private key.
 //!
 
-use crate::app::core::{MsgId, MSGID_SIZE};
-use crate::mss;
-use crate::pb3::{self, Absorb, Mask, Result};
-use crate::spongos::Spongos;
-use crate::trits::{TritSlice, TritSliceMut};
-
-/// Type of `SignedPacket` message content.
-pub const TYPE: &str = "MAM9SIGNEDPACKET";
-
-/// Size of `SignedPacket` message content.
-///
-/// # Arguments
-///
-/// * `public_trytes` -- size of public payload in trytes.
-///
-/// * `masked_trytes` -- size of masked payload in trytes.
-///
-/// * `sk` -- channel owner's MSS private key.
-pub fn sizeof(public_trytes: usize, masked_trytes: usize, sk: &mss::PrivateKey) -> usize {
-    0
-    // join link msgid;
-        + pb3::sizeof_ntrytes(MSGID_SIZE / 3)
-    // absorb trytes public_payload;
-        + pb3::sizeof_trytes(public_trytes)
-    // mask trytes masked_payload;
-        + pb3::sizeof_trytes(masked_trytes)
-    // mssig;
-        + pb3::mssig::sizeof_mssig(sk)
-}
-
-/// Wrap `SignedPacket` content.
-///
-/// # Arguments
-///
-/// * `msgid` -- link to the base message.
-///
-/// * `slink` -- spongos instance of the message linked by `msgid`.
-///
-/// * `public_payload` -- public payload.
-///
-/// * `masked_payload` -- masked payload.
-///
-/// * `sk` -- channel owner's MSS private key.
-///
-/// * `s` -- current spongos instance.
-///
-/// * `b` -- output buffer.
-pub fn wrap(
-    msgid: &MsgId,
-    slink: &mut Spongos,
-    public_payload: &pb3::Trytes,
-    masked_payload: &pb3::Trytes,
-    sk: &mss::PrivateKey,
-    s: &mut Spongos,
-    b: &mut TritSliceMut,
-) {
-    assert!(public_payload.size() % 3 == 0);
-    assert!(masked_payload.size() % 3 == 0);
-    pb3::join::wrap_join(msgid.id.slice(), slink, s, b);
-    public_payload.wrap_absorb(s, b);
-    masked_payload.wrap_mask(s, b);
-    pb3::mssig::squeeze_wrap_mssig(sk, s, b);
-}
-
-/// Unwrap `SignedPacket` content and recover signer's MSS public key.
-///
-/// # Arguments
-///
-/// * `lookup_link` -- lookup function taking `msgid` as input and returning
-/// spongos instance.
-///
-/// * `s` -- current spongos instance.
-///
-/// * `b` -- output buffer.
-///
-/// # Return
-///
-/// A tuple of public and masked payloads or error code.
-pub fn unwrap_recover(
-    lookup_link: impl Fn(TritSlice) -> Option<(Spongos, ())>,
-    s: &mut Spongos,
-    b: &mut TritSlice,
-) -> Result<(mss::PublicKey, pb3::Trytes, pb3::Trytes)> {
-    pb3::join::unwrap_join(lookup_link, s, b)?;
-    let public_payload = pb3::Trytes::unwrap_absorb_sized(s, b)?;
-    let masked_payload = pb3::Trytes::unwrap_mask_sized(s, b)?;
-    let mss_pk = pb3::mssig::squeeze_unwrap_mssig_recover(s, b)?;
-    Ok((mss_pk, public_payload, masked_payload))
-}
-
-/// Unwrap `SignedPacket` content and verify signature.
-///
-/// # Arguments
-///
-/// * `lookup_link` -- lookup function taking `msgid` as input and returning
-/// spongos instance.
-///
-/// * `mss_pk` -- channel owner's MSS public key.
-///
-/// * `s` -- current spongos instance.
-///
-/// * `b` -- output buffer.
-///
-/// # Return
-///
-/// A pair of public and masked payloads or error code.
-pub fn unwrap_verify(
-    lookup_link: impl Fn(TritSlice) -> Option<(Spongos, ())>,
-    mss_pk: &mss::PublicKey,
-    s: &mut Spongos,
-    b: &mut TritSlice,
-) -> Result<(pb3::Trytes, pb3::Trytes)> {
-    pb3::join::unwrap_join(lookup_link, s, b)?;
-    let public_payload = pb3::Trytes::unwrap_absorb_sized(s, b)?;
-    let masked_payload = pb3::Trytes::unwrap_mask_sized(s, b)?;
-    pb3::mssig::squeeze_unwrap_mssig_verify(mss_pk, s, b)?;
-    Ok((public_payload, masked_payload))
+crate::pb3_message! {
+    type TYPE = "MAM9SIGNEDPACKET";
+    join link msgid;
+    domain;
+    absorb trytes payload_type;
+    absorb trytes public_payload;
+    mask trytes masked_payload;
+    commit;
+    squeeze external tryte hash[78];
+    mssig(hash) sig;
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::app::channel::msg;
+    use crate::app::core::MsgId;
+    use crate::mss;
+    use crate::pb3;
     use crate::prng;
+    use crate::spongos::Spongos;
     use crate::trits::Trits;
 
     #[test]
@@ -166,11 +81,13 @@ mod test {
         let msgid = MsgId {
             id: Trits::cycle_str(81, "MSGID"),
         };
+        let payload_type = pb3::Trytes(Trits::cycle_str(9, "APP9CODEC"));
         let public_payload = pb3::Trytes(Trits::cycle_str(555, "PUBLIC9PAYLOAD"));
         let masked_payload = pb3::Trytes(Trits::cycle_str(444, "MASKED9PAYLOAD"));
 
         // message
         let n = msg::signed_packet::sizeof(
+            payload_type.size() / 3,
             public_payload.size() / 3,
             masked_payload.size() / 3,
             &mss_sk,
@@ -185,6 +102,7 @@ mod test {
             msg::signed_packet::wrap(
                 &msgid,
                 &mut slink,
+                &payload_type,
                 &public_payload,
                 &masked_payload,
                 &mss_sk,
@@ -210,9 +128,12 @@ mod test {
                 mss_sk.public_key(),
                 &mut s,
                 &mut b,
-            );
+            )
+            .unwrap();
             assert_eq!(0, b.size());
-            assert!(r == Ok((public_payload, masked_payload)));
+            assert!(r.payload_type == payload_type);
+            assert!(r.public_payload == public_payload);
+            assert!(r.masked_payload == masked_payload);
         }
     }
 }
@@ -0,0 +1,182 @@
+//! C bindings for channel message content.
+//!
+//! Each message type gets one `mam_<message>_sizeof`, `mam_<message>_wrap`
+//! and `mam_<message>_unwrap_*` triple mirroring its Rust counterpart in
+//! `crate::app::channel::msg`. Only [`crate::app::channel::msg::signed_packet`]
+//! exists in this crate so far; tagged and keyload messages follow the same
+//! shape once they're added.
+
+use std::ffi::c_void;
+
+use crate::app::channel::msg::signed_packet;
+use crate::app::core::{MsgId, MSGID_SIZE};
+use crate::capi::handle::{CMssPrivateKey, CMssPublicKey, CSpongos};
+use crate::capi::result::{CLinkLookupFn, COption, CResult};
+use crate::capi::trits::{copy_to_raw, trits_from_raw, trits_to_vec, CTrit};
+use crate::pb3;
+use crate::trits::Trits;
+
+/// Size of a `SignedPacket` message content; see
+/// [`signed_packet::sizeof`].
+#[no_mangle]
+pub extern "C" fn mam_signed_packet_sizeof(
+    payload_type_trytes: usize,
+    public_trytes: usize,
+    masked_trytes: usize,
+    sk: *const CMssPrivateKey,
+) -> usize {
+    let sk = unsafe { &(*sk).0 };
+    signed_packet::sizeof(payload_type_trytes, public_trytes, masked_trytes, sk)
+}
+
+/// Wrap a `SignedPacket` message content into `out`; see
+/// [`signed_packet::wrap`].
+///
+/// # Safety
+///
+/// `msgid`/`payload_type`/`public_payload`/`masked_payload` must be valid
+/// [`CTrit`] buffers of their stated lengths, `slink`/`sk`/`s` must be valid
+/// handles, and `out` must be valid for writes of `out_len` bytes with
+/// `out_len == mam_signed_packet_sizeof(..)`.
+#[no_mangle]
+pub unsafe extern "C" fn mam_signed_packet_wrap(
+    msgid: *const CTrit,
+    msgid_len: usize,
+    slink: *mut CSpongos,
+    payload_type: *const CTrit,
+    payload_type_len: usize,
+    public_payload: *const CTrit,
+    public_payload_len: usize,
+    masked_payload: *const CTrit,
+    masked_payload_len: usize,
+    sk: *const CMssPrivateKey,
+    s: *mut CSpongos,
+    out: *mut CTrit,
+    out_len: usize,
+) {
+    assert_eq!(MSGID_SIZE, msgid_len);
+    let msgid = MsgId {
+        id: trits_from_raw(msgid, msgid_len),
+    };
+    let payload_type = pb3::Trytes(trits_from_raw(payload_type, payload_type_len));
+    let public_payload = pb3::Trytes(trits_from_raw(public_payload, public_payload_len));
+    let masked_payload = pb3::Trytes(trits_from_raw(masked_payload, masked_payload_len));
+
+    let mut buf = Trits::zero(out_len);
+    {
+        let mut b = buf.slice_mut();
+        signed_packet::wrap(
+            &msgid,
+            &mut (*slink).0,
+            &payload_type,
+            &public_payload,
+            &masked_payload,
+            &(*sk).0,
+            &mut (*s).0,
+            &mut b,
+        );
+    }
+    copy_to_raw(&trits_to_vec(&buf), out, out_len);
+}
+
+/// Payload type and public/masked payloads recovered by
+/// `mam_signed_packet_unwrap_verify`. Callers must check `payload_type`
+/// against the type(s) they expect -- see [`signed_packet::unwrap_verify`].
+#[repr(C)]
+pub struct CSignedPacketPayloads {
+    pub payload_type: *mut CTrit,
+    pub payload_type_len: usize,
+    pub public_payload: *mut CTrit,
+    pub public_payload_len: usize,
+    pub masked_payload: *mut CTrit,
+    pub masked_payload_len: usize,
+}
+
+fn payloads_to_c(
+    payload_type: pb3::Trytes,
+    public_payload: pb3::Trytes,
+    masked_payload: pb3::Trytes,
+) -> CSignedPacketPayloads {
+    let mut payload_type = trits_to_vec(&payload_type.0);
+    let mut public_payload = trits_to_vec(&public_payload.0);
+    let mut masked_payload = trits_to_vec(&masked_payload.0);
+    let c = CSignedPacketPayloads {
+        payload_type: payload_type.as_mut_ptr(),
+        payload_type_len: payload_type.len(),
+        public_payload: public_payload.as_mut_ptr(),
+        public_payload_len: public_payload.len(),
+        masked_payload: masked_payload.as_mut_ptr(),
+        masked_payload_len: masked_payload.len(),
+    };
+    std::mem::forget(payload_type);
+    std::mem::forget(public_payload);
+    std::mem::forget(masked_payload);
+    c
+}
+
+/// Free the buffers returned in a [`CSignedPacketPayloads`].
+///
+/// # Safety
+///
+/// `p` must have been produced by `mam_signed_packet_unwrap_verify` and not
+/// already freed.
+#[no_mangle]
+pub unsafe extern "C" fn mam_signed_packet_payloads_free(p: CSignedPacketPayloads) {
+    drop(Vec::from_raw_parts(
+        p.payload_type,
+        p.payload_type_len,
+        p.payload_type_len,
+    ));
+    drop(Vec::from_raw_parts(
+        p.public_payload,
+        p.public_payload_len,
+        p.public_payload_len,
+    ));
+    drop(Vec::from_raw_parts(
+        p.masked_payload,
+        p.masked_payload_len,
+        p.masked_payload_len,
+    ));
+}
+
+/// Unwrap a `SignedPacket` message content and verify its signature; see
+/// [`signed_packet::unwrap_verify`].
+///
+/// # Safety
+///
+/// `lookup`/`lookup_ctx` must together form a valid link-lookup callback.
+/// `lookup` hands ownership of the [`CSpongos`] handle it returns to this
+/// function, which frees it once it has cloned the `Spongos` out of it --
+/// the handle must not be read or freed again afterwards. `mss_pk`/`s` must
+/// be valid handles, and `buf` must be a valid [`CTrit`] buffer of length
+/// `buf_len`.
+#[no_mangle]
+pub unsafe extern "C" fn mam_signed_packet_unwrap_verify(
+    lookup: CLinkLookupFn,
+    lookup_ctx: *mut c_void,
+    mss_pk: *const CMssPublicKey,
+    s: *mut CSpongos,
+    buf: *const CTrit,
+    buf_len: usize,
+) -> CResult<CSignedPacketPayloads> {
+    let t = trits_from_raw(buf, buf_len);
+    let mut b = t.slice();
+    let r = signed_packet::unwrap_verify(
+        |msgid| {
+            let msgid_bytes = crate::capi::trits::trit_slice_to_vec(msgid);
+            match lookup(msgid_bytes.as_ptr(), msgid_bytes.len(), lookup_ctx) {
+                COption::Some(h) => {
+                    let owned = Box::from_raw(h);
+                    Some((owned.0.clone(), ()))
+                }
+                COption::None => None,
+            }
+        },
+        &(*mss_pk).0,
+        &mut (*s).0,
+        &mut b,
+    );
+    CResult::from_result(r.map(|fields| {
+        payloads_to_c(fields.payload_type, fields.public_payload, fields.masked_payload)
+    }))
+}
@@ -0,0 +1,77 @@
+//! Opaque handles for Rust types that have no C representation.
+//!
+//! `Spongos` and `mss` keys carry private fields and borrow-checked methods
+//! that can't be expressed in C. Each is wrapped in a one-field newtype,
+//! boxed and handed to C as a raw pointer; the corresponding `*_free`
+//! function drops the box. Handles are not thread-safe and, like any C
+//! pointer, must not be used after being freed.
+
+use crate::mss;
+use crate::spongos::Spongos;
+
+/// Opaque handle to a [`Spongos`] instance.
+pub struct CSpongos(pub(crate) Spongos);
+
+/// Create a handle to a freshly initialized [`Spongos`].
+#[no_mangle]
+pub extern "C" fn mam_spongos_init() -> *mut CSpongos {
+    Box::into_raw(Box::new(CSpongos(Spongos::init())))
+}
+
+/// Clone a [`Spongos`] handle.
+///
+/// # Safety
+///
+/// `s` must be a valid, non-null handle obtained from this module and not
+/// yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn mam_spongos_clone(s: *const CSpongos) -> *mut CSpongos {
+    Box::into_raw(Box::new(CSpongos((*s).0.clone())))
+}
+
+/// Free a [`Spongos`] handle. `s` may be null, in which case this is a no-op.
+///
+/// # Safety
+///
+/// `s` must either be null or a valid handle obtained from this module that
+/// has not already been freed.
+#[no_mangle]
+pub unsafe extern "C" fn mam_spongos_free(s: *mut CSpongos) {
+    if !s.is_null() {
+        drop(Box::from_raw(s));
+    }
+}
+
+/// Opaque handle to an [`mss::PrivateKey`].
+pub struct CMssPrivateKey(pub(crate) mss::PrivateKey);
+
+/// Free an [`mss::PrivateKey`] handle. `sk` may be null, in which case this
+/// is a no-op.
+///
+/// # Safety
+///
+/// `sk` must either be null or a valid handle obtained from this module that
+/// has not already been freed.
+#[no_mangle]
+pub unsafe extern "C" fn mam_mss_private_key_free(sk: *mut CMssPrivateKey) {
+    if !sk.is_null() {
+        drop(Box::from_raw(sk));
+    }
+}
+
+/// Opaque handle to an [`mss::PublicKey`].
+pub struct CMssPublicKey(pub(crate) mss::PublicKey);
+
+/// Free an [`mss::PublicKey`] handle. `pk` may be null, in which case this is
+/// a no-op.
+///
+/// # Safety
+///
+/// `pk` must either be null or a valid handle obtained from this module that
+/// has not already been freed.
+#[no_mangle]
+pub unsafe extern "C" fn mam_mss_public_key_free(pk: *mut CMssPublicKey) {
+    if !pk.is_null() {
+        drop(Box::from_raw(pk));
+    }
+}
@@ -0,0 +1,29 @@
+//! C ABI bindings for channel message wrap/unwrap.
+//!
+//! The rest of the crate is pure Rust: it leans on trinary slices
+//! (`TritSlice`/`TritSliceMut`), `Spongos` and `Result`-returning
+//! `unwrap_*` functions, none of which can cross an FFI boundary as-is.
+//! This module exposes the channel message API behind `#[no_mangle] extern
+//! "C"` functions built only from `repr(C)` types:
+//!
+//! * [`result::CResult`] / [`result::COption`] stand in for `Result`/`Option`,
+//! * [`handle::CSpongos`] / [`handle::CMssPrivateKey`] /
+//!   [`handle::CMssPublicKey`] are opaque handles with `*_free` destructors,
+//! * the link-lookup `impl Fn(TritSlice) -> Option<(Spongos, ())>` closure
+//!   becomes a [`result::CLinkLookupFn`] function pointer plus a `ctx`
+//!   user-data pointer,
+//! * every trinary buffer is a length-tagged [`trits::CTrit`] pointer (see
+//!   that module for the byte encoding).
+//!
+//! [`channel`] wires these together for the concrete message types in
+//! `crate::app::channel::msg`.
+
+pub mod channel;
+
+mod handle;
+mod result;
+mod trits;
+
+pub use handle::{CMssPrivateKey, CMssPublicKey, CSpongos};
+pub use result::{CErr, CLinkLookupFn, COption, CResult};
+pub use trits::CTrit;
@@ -0,0 +1,58 @@
+//! C-safe stand-ins for `Result` and `Option`.
+//!
+//! Rust's `Result<T, E>` and the `Option<(Spongos, ())>` returned by a
+//! link-lookup closure aren't FFI-safe once `T`/`E` themselves aren't, so
+//! every value crossing [`super`]'s boundary is funneled through
+//! [`CResult`] or [`COption`] instead, both plain `repr(C)` tagged unions.
+
+use crate::pb3::err::Err;
+
+/// C representation of [`crate::pb3::err::Err`]: the discriminant of the
+/// Rust enum, reinterpreted as a plain code.
+#[repr(C)]
+pub struct CErr(pub i32);
+
+impl From<Err> for CErr {
+    fn from(e: Err) -> Self {
+        CErr(e as i32)
+    }
+}
+
+/// C-safe `Result<T, Err>`.
+#[repr(C)]
+pub enum CResult<T> {
+    Ok(T),
+    Err(CErr),
+}
+
+impl<T> CResult<T> {
+    pub(crate) fn from_result(r: Result<T, Err>) -> Self {
+        match r {
+            Ok(v) => CResult::Ok(v),
+            Err(e) => CResult::Err(e.into()),
+        }
+    }
+}
+
+/// C-safe `Option<T>`.
+#[repr(C)]
+pub enum COption<T> {
+    Some(T),
+    None,
+}
+
+/// C function pointer implementing the link-lookup callback that
+/// [`crate::pb3::join::unwrap_join`] takes as an `impl Fn(TritSlice) ->
+/// Option<(Spongos, ())>` closure on the Rust side.
+///
+/// Given the looked-up message id (as a length-tagged [`super::CTrit`]
+/// buffer) and the opaque `ctx` passed alongside this function pointer to
+/// the wrapping `mam_*_unwrap_*` call, it must return a fresh (not shared
+/// with any other handle) handle to the linked message's spongos state, or
+/// `COption::None` if the id is unknown. Ownership of any returned handle
+/// transfers to the callee, which consumes and frees it before returning.
+pub type CLinkLookupFn = unsafe extern "C" fn(
+    msgid: *const super::CTrit,
+    msgid_len: usize,
+    ctx: *mut std::ffi::c_void,
+) -> COption<*mut super::CSpongos>;
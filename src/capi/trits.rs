@@ -0,0 +1,52 @@
+//! Byte encoding for trinary buffers crossing the C ABI.
+//!
+//! C has no notion of a balanced-ternary trit, so every trinary buffer that
+//! crosses [`super`]'s functions is passed as a length-tagged pointer to
+//! [`CTrit`] bytes, one byte per trit: `0` for `-1`, `1` for `0`, `2` for
+//! `1`. This is the only encoding [`super`] uses; callers on the C side must
+//! produce and consume buffers in this form.
+
+use crate::trits::{TritSlice, Trits};
+
+/// One trit encoded as a single byte (`0`, `1` or `2`; see the [module
+/// docs](self)).
+pub type CTrit = u8;
+
+/// Decode `len` [`CTrit`] bytes at `ptr` into a fresh [`Trits`] buffer.
+///
+/// # Safety
+///
+/// `ptr` must be valid for reads of `len` bytes, each holding `0`, `1` or `2`.
+pub(crate) unsafe fn trits_from_raw(ptr: *const CTrit, len: usize) -> Trits {
+    let bytes = std::slice::from_raw_parts(ptr, len);
+    let mut t = Trits::zero(len);
+    let mut b = t.slice_mut();
+    for &byte in bytes {
+        b.advance(1).put1(byte as i8 - 1);
+    }
+    t
+}
+
+/// Encode `t` as a vector of [`CTrit`] bytes, one per trit.
+pub(crate) fn trits_to_vec(t: &Trits) -> Vec<CTrit> {
+    trit_slice_to_vec(t.slice())
+}
+
+/// Encode a [`TritSlice`] as a vector of [`CTrit`] bytes, one per trit.
+pub(crate) fn trit_slice_to_vec(mut s: TritSlice) -> Vec<CTrit> {
+    let mut out = Vec::with_capacity(s.size());
+    while s.size() > 0 {
+        out.push((s.advance(1).get1() + 1) as u8);
+    }
+    out
+}
+
+/// Copy `src` into the `len` [`CTrit`] bytes at `dst`.
+///
+/// # Safety
+///
+/// `dst` must be valid for writes of `len` bytes, and `src.len() == len`.
+pub(crate) unsafe fn copy_to_raw(src: &[CTrit], dst: *mut CTrit, len: usize) {
+    debug_assert_eq!(src.len(), len);
+    std::ptr::copy_nonoverlapping(src.as_ptr(), dst, len);
+}
@@ -0,0 +1,20 @@
+//! Domain separation for signed PB3 messages.
+//!
+//! A `commit`/`squeeze external`/`mssig` sequence signs whatever the running
+//! `Spongos` has absorbed so far; nothing in that alone binds the signature
+//! to a particular message type or application, so a signature produced for
+//! one context could in principle be replayed against another that leads to
+//! the same spongos state. [`absorb_domain`] lets a signed message absorb a
+//! fixed, never-transmitted constant -- typically its own `TYPE` string --
+//! before `commit`, so the signed hash commits to the domain as well as the
+//! payloads. Both wrap and unwrap sides must call it with the same constant.
+
+use crate::spongos::Spongos;
+use crate::trits::Trits;
+
+/// Absorb `domain` externally: it is never written to the wrap buffer, so
+/// the unwrapping side must already know `domain` and absorb the same
+/// constant to reproduce the signed hash.
+pub fn absorb_domain(s: &mut Spongos, domain: &str) {
+    s.absorb(Trits::from_str(domain).unwrap().slice());
+}
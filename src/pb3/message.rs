@@ -0,0 +1,349 @@
+//! `pb3_message!` schema macro.
+//!
+//! Message content modules such as [`crate::app::channel::msg::signed_packet`]
+//! would otherwise hand-write three functions (`sizeof`, `wrap`,
+//! `unwrap_verify`) that must stay byte-for-byte consistent with the PB3
+//! command list documented above them (`join link msgid; absorb trytes ...;
+//! mask trytes ...; commit; squeeze external ...; mssig(hash) sig;`). Every
+//! new message type would re-derive the same boilerplate over the
+//! [`crate::pb3::Absorb`], [`crate::pb3::Mask`], [`crate::pb3::join`],
+//! [`crate::pb3::oneof`] and [`crate::pb3::mssig`] building blocks, and a typo
+//! in either the doc comment or the code would be easy to miss in review.
+//!
+//! `pb3_message!` takes the command list directly -- the same grammar already
+//! used in the doc comments above message content modules -- and expands it
+//! into `TYPE`, `Fields`, `sizeof`, `wrap`, `unwrap_verify` and
+//! `unwrap_recover` items, emitted straight into the invoking module (so a
+//! message module's own doc comment and tests stay hand-written, only the
+//! codec boilerplate is generated). [`crate::app::channel::msg::signed_packet`]
+//! is the first and, so far, only user.
+//!
+//! # Grammar
+//!
+//! ```ignore
+//! pb3_message! {
+//!     type TYPE = "...";
+//!     join link LINK_FIELD;
+//!     domain;                              // optional; see below
+//!     absorb trytes FIELD;                 // any number, in order
+//!     absorb oneof FIELD;                  // any number, in order, but
+//!                                          // only after all `absorb trytes`
+//!     mask trytes FIELD;                   // any number, in order
+//!     commit;
+//!     squeeze external tryte HASH[SIZE];
+//!     mssig(HASH) SIG;                      // HASH must match the name above
+//! }
+//! ```
+//!
+//! `join link`, `commit`, `squeeze external tryte NAME[N]` and `mssig(NAME)
+//! sig` thread the running `Spongos` in exactly the order the hand-written
+//! code did (the last three together are exactly what
+//! [`crate::pb3::mssig::sizeof_mssig`] / `squeeze_wrap_mssig` /
+//! `squeeze_unwrap_mssig_verify` / `squeeze_unwrap_mssig_recover` already
+//! encapsulate), so generated and previously hand-written output were
+//! bit-identical for the same schema. The macro checks at compile time (via
+//! [`str_eq`]) that the identifier named in `mssig(..)` is the same one
+//! `squeeze`d, since nothing else in the grammar ties them together.
+//!
+//! `domain;` absorbs the message's own `TYPE` string externally (see
+//! [`crate::pb3::domain::absorb_domain`]) right after `join link`, the same
+//! position `signed_packet` absorbs it in -- this is what lets the macro
+//! reproduce a domain-separated message like `signed_packet` exactly. It is
+//! optional because not every signed message needs to opt into domain
+//! separation, and unsigned messages don't absorb a domain tag at all.
+//!
+//! `absorb oneof` covers the `oneof` modifier from [`crate::pb3::oneof`]
+//! (encoded like a `tryte`, fixed size, so no argument in `sizeof`; `wrap`
+//! takes the `OneOf` value like any other field). The
+//! grammar only supports it after every `absorb trytes` field -- arbitrary
+//! interleaving of `absorb trytes`/`absorb oneof` isn't supported, since
+//! `macro_rules!` can't branch on a repeated fragment's kind mid-repetition
+//! without a recursive tt-muncher, and no current message needs it.
+//!
+//! The generated `Fields` struct holds every `absorb`/`mask` field in
+//! declaration order; `wrap` is always bit-identical to a hand-written
+//! equivalent, since "bit-identical" is a property of the wrapped buffer, not
+//! of the Rust-level return shape.
+
+/// Compare two strings for equality in a `const` context.
+///
+/// Used by `pb3_message!` to check, at compile time, that the identifier
+/// named in `mssig(..)` is the same one bound by the preceding `squeeze
+/// external tryte NAME[..]` -- `macro_rules!` matchers can't themselves
+/// enforce that two independently captured identifiers are equal.
+pub const fn str_eq(a: &str, b: &str) -> bool {
+    let a = a.as_bytes();
+    let b = b.as_bytes();
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Expand a `message { ... }` PB3 schema into `TYPE`, `Fields`, `sizeof`,
+/// `wrap`, `unwrap_verify` and `unwrap_recover` items in the invoking module.
+///
+/// See the [module docs](self) for the full grammar.
+#[macro_export]
+macro_rules! pb3_message {
+    (
+        type TYPE = $type_str:expr;
+        join link $link:ident;
+        $(domain $domain_semi:tt)?
+        $(absorb trytes $absorb_field:ident;)*
+        $(absorb oneof $oneof_field:ident;)*
+        $(mask trytes $mask_field:ident;)*
+        commit;
+        squeeze external tryte $hash:ident [ $hash_size:expr ];
+        mssig($mssig_hash:ident) $sig:ident;
+    ) => {
+        /// Type of this message content.
+        pub const TYPE: &str = $type_str;
+
+        const _: () = assert!(
+            $crate::pb3::message::str_eq(stringify!($hash), stringify!($mssig_hash)),
+            "pb3_message!: mssig(..) must name the squeezed hash",
+        );
+
+        /// Fields recovered from an unwrapped message.
+        pub struct Fields {
+            $(pub $absorb_field: $crate::pb3::Trytes,)*
+            $(pub $oneof_field: $crate::pb3::oneof::OneOf,)*
+            $(pub $mask_field: $crate::pb3::Trytes,)*
+        }
+
+        /// Size of this message content.
+        pub fn sizeof(
+            $($absorb_field: usize,)*
+            $($mask_field: usize,)*
+            sk: &$crate::mss::PrivateKey,
+        ) -> usize {
+            0
+                // join link;
+                + $crate::pb3::sizeof_ntrytes($crate::app::core::MSGID_SIZE / 3)
+                $(+ $crate::pb3::sizeof_trytes($absorb_field))*
+                $(+ { let _ = stringify!($oneof_field); $crate::pb3::oneof::sizeof_oneof() })*
+                $(+ $crate::pb3::sizeof_trytes($mask_field))*
+                // commit; squeeze external tryte hash[..]; mssig(hash) sig;
+                + $crate::pb3::mssig::sizeof_mssig(sk)
+        }
+
+        /// Wrap this message content.
+        pub fn wrap(
+            $link: &$crate::app::core::MsgId,
+            slink: &mut $crate::spongos::Spongos,
+            $($absorb_field: &$crate::pb3::Trytes,)*
+            $($oneof_field: &$crate::pb3::oneof::OneOf,)*
+            $($mask_field: &$crate::pb3::Trytes,)*
+            sk: &$crate::mss::PrivateKey,
+            s: &mut $crate::spongos::Spongos,
+            b: &mut $crate::trits::TritSliceMut,
+        ) {
+            $crate::pb3::join::wrap_join($link.id.slice(), slink, s, b);
+            $({ let _ = stringify!($domain_semi); $crate::pb3::domain::absorb_domain(s, TYPE); })?
+            $($crate::pb3::Absorb::wrap_absorb($absorb_field, s, b);)*
+            $($crate::pb3::Absorb::wrap_absorb($oneof_field, s, b);)*
+            $($crate::pb3::Mask::wrap_mask($mask_field, s, b);)*
+            $crate::pb3::mssig::squeeze_wrap_mssig(sk, s, b);
+        }
+
+        /// Unwrap this message content and recover the signer's MSS public key.
+        pub fn unwrap_recover(
+            lookup_link: impl Fn($crate::trits::TritSlice) -> Option<($crate::spongos::Spongos, ())>,
+            s: &mut $crate::spongos::Spongos,
+            b: &mut $crate::trits::TritSlice,
+        ) -> $crate::pb3::Result<($crate::mss::PublicKey, Fields)> {
+            $crate::pb3::join::unwrap_join(lookup_link, s, b)?;
+            $({ let _ = stringify!($domain_semi); $crate::pb3::domain::absorb_domain(s, TYPE); })?
+            $(let $absorb_field = <$crate::pb3::Trytes as $crate::pb3::Absorb>::unwrap_absorb_sized(s, b)?;)*
+            $(let $oneof_field = <$crate::pb3::oneof::OneOf as $crate::pb3::Absorb>::unwrap_absorb_sized(s, b)?;)*
+            $(let $mask_field = <$crate::pb3::Trytes as $crate::pb3::Mask>::unwrap_mask_sized(s, b)?;)*
+            let mss_pk = $crate::pb3::mssig::squeeze_unwrap_mssig_recover(s, b)?;
+            Ok((mss_pk, Fields { $($absorb_field,)* $($oneof_field,)* $($mask_field,)* }))
+        }
+
+        /// Unwrap this message content and verify its signature.
+        pub fn unwrap_verify(
+            lookup_link: impl Fn($crate::trits::TritSlice) -> Option<($crate::spongos::Spongos, ())>,
+            mss_pk: &$crate::mss::PublicKey,
+            s: &mut $crate::spongos::Spongos,
+            b: &mut $crate::trits::TritSlice,
+        ) -> $crate::pb3::Result<Fields> {
+            $crate::pb3::join::unwrap_join(lookup_link, s, b)?;
+            $({ let _ = stringify!($domain_semi); $crate::pb3::domain::absorb_domain(s, TYPE); })?
+            $(let $absorb_field = <$crate::pb3::Trytes as $crate::pb3::Absorb>::unwrap_absorb_sized(s, b)?;)*
+            $(let $oneof_field = <$crate::pb3::oneof::OneOf as $crate::pb3::Absorb>::unwrap_absorb_sized(s, b)?;)*
+            $(let $mask_field = <$crate::pb3::Trytes as $crate::pb3::Mask>::unwrap_mask_sized(s, b)?;)*
+            $crate::pb3::mssig::squeeze_unwrap_mssig_verify(mss_pk, s, b)?;
+            Ok(Fields { $($absorb_field,)* $($oneof_field,)* $($mask_field,)* })
+        }
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use crate::app::core::MsgId;
+    use crate::prng;
+    use crate::trits::Trits;
+    use crate::{mss, pb3};
+
+    // A message with no domain separation, to exercise the macro on its own
+    // (`crate::app::channel::msg::signed_packet` exercises the `domain;`
+    // path as the macro's real, non-test user).
+    mod plain_packet {
+        crate::pb3_message! {
+            type TYPE = "MAM9PLAINPACKET";
+            join link msgid;
+            absorb trytes public_payload;
+            mask trytes masked_payload;
+            commit;
+            squeeze external tryte hash[78];
+            mssig(hash) sig;
+        }
+    }
+
+    // A message with an `absorb oneof` field, to exercise that arm of the
+    // macro on its own (no current message content module needs `oneof` yet).
+    mod oneof_packet {
+        crate::pb3_message! {
+            type TYPE = "MAM9ONEOFPACKET";
+            join link msgid;
+            absorb trytes public_payload;
+            absorb oneof kind;
+            mask trytes masked_payload;
+            commit;
+            squeeze external tryte hash[78];
+            mssig(hash) sig;
+        }
+    }
+
+    #[test]
+    fn wrap_unwrap() {
+        let mss_nonce = Trits::from_str("MSSNONCE").unwrap();
+
+        let prng = prng::dbg_init_str("PRNGKEY");
+        let d = 2;
+        let mss_sk = mss::PrivateKey::gen(&prng, mss_nonce.slice(), d);
+
+        let msgid = MsgId {
+            id: Trits::cycle_str(81, "MSGID"),
+        };
+        let public_payload = pb3::Trytes(Trits::cycle_str(555, "PUBLIC9PAYLOAD"));
+        let masked_payload = pb3::Trytes(Trits::cycle_str(444, "MASKED9PAYLOAD"));
+
+        let n = plain_packet::sizeof(
+            public_payload.size() / 3,
+            masked_payload.size() / 3,
+            &mss_sk,
+        );
+        let mut buf = Trits::zero(n);
+
+        {
+            let mut s = crate::spongos::Spongos::init();
+            let mut b = buf.slice_mut();
+            let mut slink = crate::spongos::Spongos::init();
+            plain_packet::wrap(
+                &msgid,
+                &mut slink,
+                &public_payload,
+                &masked_payload,
+                &mss_sk,
+                &mut s,
+                &mut b,
+            );
+            assert_eq!(0, b.size());
+        }
+
+        {
+            let mut s = crate::spongos::Spongos::init();
+            let mut b = buf.slice();
+            let slink = crate::spongos::Spongos::init();
+            let r = plain_packet::unwrap_verify(
+                |m| {
+                    if m == msgid.id.slice() {
+                        Some((slink.clone(), ()))
+                    } else {
+                        None
+                    }
+                },
+                mss_sk.public_key(),
+                &mut s,
+                &mut b,
+            )
+            .unwrap();
+            assert_eq!(0, b.size());
+            assert!(r.public_payload == public_payload);
+            assert!(r.masked_payload == masked_payload);
+        }
+    }
+
+    #[test]
+    fn wrap_unwrap_oneof() {
+        let mss_nonce = Trits::from_str("MSSNONCE").unwrap();
+
+        let prng = prng::dbg_init_str("PRNGKEY");
+        let d = 2;
+        let mss_sk = mss::PrivateKey::gen(&prng, mss_nonce.slice(), d);
+
+        let msgid = MsgId {
+            id: Trits::cycle_str(81, "MSGID"),
+        };
+        let public_payload = pb3::Trytes(Trits::cycle_str(555, "PUBLIC9PAYLOAD"));
+        let kind = pb3::oneof::oneof(crate::trits::Trint3(5));
+        let masked_payload = pb3::Trytes(Trits::cycle_str(444, "MASKED9PAYLOAD"));
+
+        let n = oneof_packet::sizeof(
+            public_payload.size() / 3,
+            masked_payload.size() / 3,
+            &mss_sk,
+        );
+        let mut buf = Trits::zero(n);
+
+        {
+            let mut s = crate::spongos::Spongos::init();
+            let mut b = buf.slice_mut();
+            let mut slink = crate::spongos::Spongos::init();
+            oneof_packet::wrap(
+                &msgid,
+                &mut slink,
+                &public_payload,
+                &kind,
+                &masked_payload,
+                &mss_sk,
+                &mut s,
+                &mut b,
+            );
+            assert_eq!(0, b.size());
+        }
+
+        {
+            let mut s = crate::spongos::Spongos::init();
+            let mut b = buf.slice();
+            let slink = crate::spongos::Spongos::init();
+            let r = oneof_packet::unwrap_verify(
+                |m| {
+                    if m == msgid.id.slice() {
+                        Some((slink.clone(), ()))
+                    } else {
+                        None
+                    }
+                },
+                mss_sk.public_key(),
+                &mut s,
+                &mut b,
+            )
+            .unwrap();
+            assert_eq!(0, b.size());
+            assert!(r.public_payload == public_payload);
+            assert!(r.kind == kind);
+            assert!(r.masked_payload == masked_payload);
+        }
+    }
+}
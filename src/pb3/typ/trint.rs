@@ -0,0 +1,33 @@
+//! PB3 `tryte` codeword type.
+
+use crate::pb3::cmd::absorb::Absorb;
+use crate::pb3::err::{guard, Err, Result};
+use crate::spongos::Spongos;
+use crate::trits::{self, TritSlice, TritSliceMut};
+
+/// A single `tryte` codeword, encoded and absorbed as one 3-trit `trint3`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Trint3(pub trits::Trint3);
+
+/// `tryte` is represented by [`Trint3`].
+pub type Tryte = Trint3;
+
+/// Size of a `tryte` codeword, in trits.
+pub fn sizeof_tryte() -> usize {
+    3
+}
+
+impl Absorb for Trint3 {
+    fn wrap_absorb(&self, s: &mut Spongos, b: &mut TritSliceMut) {
+        let b0 = b.advance(3);
+        b0.put3(self.0);
+        s.absorb(b0.as_const());
+    }
+
+    fn unwrap_absorb_sized(s: &mut Spongos, b: &mut TritSlice) -> Result<Self> {
+        guard(3 <= b.size(), Err::Eof)?;
+        let b0 = b.advance(3);
+        s.absorb(b0);
+        Ok(Trint3(b0.get3()))
+    }
+}
@@ -0,0 +1,168 @@
+//! Spongos-based pseudo-random generator.
+//!
+//! [`Prng`] is keyed with a secret and derives randomness by absorbing the
+//! key plus caller-supplied nonces into a fresh [`Spongos`] instance and
+//! squeezing trits out of it; [`mss::PrivateKey::gen`] uses this to turn a
+//! single secret into one private key per MSS leaf, nonce-scoped so leaves
+//! don't share randomness. [`Prng::gen_trits`] is the native, allocation-light
+//! entry point for that use case.
+//!
+//! [`Prng`] also implements `rand_core`'s [`RngCore`]/[`SeedableRng`] so it
+//! can be used with the wider `rand` ecosystem -- filling arbitrary byte
+//! buffers, or combined with `rand`'s distributions -- without every
+//! consumer having to learn the trit-oriented API above. The adapter drives
+//! the same [`Spongos`] squeeze, batching it and converting the produced
+//! trytes into bytes deterministically; it carries its own counter so
+//! repeated calls don't replay the same sponge output.
+
+use rand_core::{Error, RngCore, SeedableRng};
+
+use crate::spongos::Spongos;
+use crate::trits::{TritSlice, Trits};
+
+/// Size of the PRNG secret key in trits.
+pub const KEY_SIZE: usize = 243;
+
+/// Sponge-based PRNG keyed with a secret.
+#[derive(Clone)]
+pub struct Prng {
+    secret_key: Trits,
+    /// Domain-separating counter consumed only by the `RngCore` adapter.
+    counter: u64,
+}
+
+impl Prng {
+    /// Initialize a PRNG with `secret_key`.
+    pub fn init(secret_key: Trits) -> Self {
+        assert_eq!(KEY_SIZE, secret_key.size());
+        Prng {
+            secret_key,
+            counter: 0,
+        }
+    }
+
+    /// Squeeze `n` trits of randomness scoped to `nonces`.
+    ///
+    /// Distinct `nonces` deterministically yield independent output even
+    /// though they share the same secret key; this is the native,
+    /// trit-oriented fast path, e.g. for deriving one MSS private key per
+    /// leaf index from a single PRNG.
+    pub fn gen_trits(&self, nonces: &[TritSlice], n: usize) -> Trits {
+        let mut s = Spongos::init();
+        s.absorb(self.secret_key.slice());
+        for nonce in nonces {
+            s.absorb(*nonce);
+        }
+        s.commit();
+        let mut rnd = Trits::zero(n);
+        s.squeeze(rnd.slice_mut());
+        rnd
+    }
+}
+
+/// Initialize a PRNG with a debug (not cryptographically meaningful) ASCII
+/// seed string. Only meant for tests.
+pub fn dbg_init_str(seed: &str) -> Prng {
+    Prng::init(Trits::cycle_str(KEY_SIZE, seed))
+}
+
+/// Number of trytes the `RngCore` adapter squeezes per counter tick.
+const RNG_CHUNK_TRYTES: usize = 16;
+
+impl Prng {
+    /// Squeeze the next `RNG_CHUNK_TRYTES` worth of trits, scoped to the
+    /// current counter value, and advance the counter.
+    fn next_chunk(&mut self) -> Trits {
+        // Base-3 digits of the counter, one trit each; 3^41 > 2^64, so every
+        // counter value gets a distinct nonce.
+        let mut nonce = Trits::zero(41);
+        let mut b = nonce.slice_mut();
+        let mut c = self.counter;
+        for _ in 0..41 {
+            b.advance(1).put1((c % 3) as i8 - 1);
+            c /= 3;
+        }
+        self.counter = self.counter.wrapping_add(1);
+        self.gen_trits(&[nonce.slice()], RNG_CHUNK_TRYTES * 3)
+    }
+}
+
+impl RngCore for Prng {
+    fn next_u32(&mut self) -> u32 {
+        let mut buf = [0u8; 4];
+        self.fill_bytes(&mut buf);
+        u32::from_le_bytes(buf)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut buf = [0u8; 8];
+        self.fill_bytes(&mut buf);
+        u64::from_le_bytes(buf)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        // One tryte is 3 trits, 27 balanced values in [-13, 13]; a single
+        // tryte only covers 27 of 256 possible byte values, so pack two
+        // trytes into one base-27 register covering [0, 728] and reject the
+        // tail above the largest multiple of 256 (512) before reducing mod
+        // 256. This keeps every emitted byte uniform over 0..=255 at the
+        // cost of ~30% of register values being discarded.
+        const REGISTER_SIZE: u32 = 27 * 27;
+        const REJECT_ABOVE: u32 = (REGISTER_SIZE / 256) * 256;
+
+        let mut filled = 0;
+        while filled < dest.len() {
+            let chunk = self.next_chunk();
+            let mut trytes = chunk.slice();
+            while filled < dest.len() && trytes.size() >= 6 {
+                let hi = (trytes.advance(3).get3().0 + 13) as u32;
+                let lo = (trytes.advance(3).get3().0 + 13) as u32;
+                let v = hi * 27 + lo;
+                if v < REJECT_ABOVE {
+                    dest[filled] = (v % 256) as u8;
+                    filled += 1;
+                }
+            }
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+/// Seed for [`Prng::from_seed`], one byte per secret-key tryte.
+///
+/// `SeedableRng::Seed` must implement `Default` + `AsMut<[u8]>`, but the
+/// standard library only implements `Default` for arrays up to length 32,
+/// and `KEY_SIZE / 3` (81) is well past that -- so a bare `[u8; 81]` can't be
+/// used directly and this newtype carries the array instead.
+#[derive(Clone)]
+pub struct PrngSeed(pub [u8; KEY_SIZE / 3]);
+
+impl Default for PrngSeed {
+    fn default() -> Self {
+        PrngSeed([0u8; KEY_SIZE / 3])
+    }
+}
+
+impl AsMut<[u8]> for PrngSeed {
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut self.0
+    }
+}
+
+impl SeedableRng for Prng {
+    type Seed = PrngSeed;
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        let mut secret_key = Trits::zero(KEY_SIZE);
+        let mut b = secret_key.slice_mut();
+        for byte in seed.0.iter() {
+            let v = (byte % 27) as i8 - 13;
+            b.advance(3).put3(crate::trits::Trint3(v));
+        }
+        Prng::init(secret_key)
+    }
+}